@@ -0,0 +1,75 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoError {
+    message: String,
+}
+
+/// The pieces produced by [`encrypt`]: the blob that gets persisted and the key
+/// that must be carried by the caller since it is never stored.
+pub struct EncryptedUrl {
+    pub ciphertext: String,
+    pub key: String,
+}
+
+/// Encrypts `long_url` under a freshly generated XChaCha20-Poly1305 key and nonce.
+///
+/// The returned `ciphertext` is `base64url(nonce || ciphertext)` and is safe to
+/// persist as-is; the returned `key` is `base64url(key)` and must be kept by the
+/// caller, since `Persistence` never sees it.
+pub fn encrypt(long_url: &str) -> EncryptedUrl {
+    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+    let cipher = XChaCha20Poly1305::new(&key);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, long_url.as_bytes())
+        .expect("XChaCha20-Poly1305 encryption should never fail");
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+
+    EncryptedUrl {
+        ciphertext: URL_SAFE_NO_PAD.encode(payload),
+        key: URL_SAFE_NO_PAD.encode(key),
+    }
+}
+
+/// Decrypts a `base64url(nonce || ciphertext)` blob produced by [`encrypt`] with `key`.
+///
+/// Returns a [`CryptoError`] rather than plaintext if `key` is malformed or AEAD
+/// authentication fails, so callers never accidentally leak the stored blob.
+pub fn decrypt(ciphertext: &str, key: &str) -> Result<String, CryptoError> {
+    let key_bytes = URL_SAFE_NO_PAD.decode(key).map_err(|_| CryptoError {
+        message: "malformed key".to_string(),
+    })?;
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(ciphertext)
+        .map_err(|_| CryptoError {
+            message: "malformed ciphertext".to_string(),
+        })?;
+
+    if payload.len() < 24 {
+        return Err(CryptoError {
+            message: "ciphertext too short to contain a nonce".to_string(),
+        });
+    }
+    let (nonce, ciphertext) = payload.split_at(24);
+
+    let cipher = XChaCha20Poly1305::new_from_slice(&key_bytes).map_err(|_| CryptoError {
+        message: "malformed key".to_string(),
+    })?;
+
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError {
+            message: "decryption failed".to_string(),
+        })?;
+
+    String::from_utf8(plaintext).map_err(|_| CryptoError {
+        message: "decrypted payload was not valid utf8".to_string(),
+    })
+}
@@ -1,66 +1,158 @@
 #[macro_use]
 extern crate lazy_static;
+use chrono::{Duration, Utc};
 use clap::{Parser, Subcommand};
 use persistence::Persistence;
-use std::collections::hash_map::DefaultHasher;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
-use std::time::Instant;
+mod crypto;
 mod persistence;
+mod search;
+mod serve;
 mod url_entry;
 use crate::url_entry::{UrlEntry, UrlEntryRequest};
 use colored::Colorize;
 
 const ALPHABET: &'static [char] = &[
-    '0', '1', '2', '3', '4', '5', '6', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j',
-    'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C',
-    'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V',
-    'W', 'X', 'Y', 'Z',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i',
+    'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B',
+    'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U',
+    'V', 'W', 'X', 'Y', 'Z',
 ];
 
-fn create_hash(long_url: &str) -> Option<u64> {
-    let mut hasher = DefaultHasher::new();
-    long_url.hash(&mut hasher);
-    Some(hasher.finish())
+/// Default length, in base62 characters, of a freshly generated short code.
+const DEFAULT_CODE_LENGTH: usize = 6;
+
+/// Hashes `input` with SHA-256 and returns the first 8 bytes as a big-endian `u64`.
+///
+/// Unlike `DefaultHasher`, SHA-256's output is stable across Rust versions and
+/// platforms, so the same input always maps to the same short code.
+fn create_hash(input: &str) -> u64 {
+    let digest = Sha256::digest(input.as_bytes());
+    u64::from_be_bytes(
+        digest[0..8]
+            .try_into()
+            .expect("a SHA-256 digest is at least 8 bytes"),
+    )
 }
 
-fn encode_hash(mut hash: u64) -> String {
-    let mut encoded = String::new();
+/// Base62-encodes the low `length` digits of `hash` (that is, `hash mod 62^length`),
+/// most-significant digit first. Higher digits of `hash` are dropped.
+fn encode_hash(mut hash: u64, length: usize) -> String {
+    let mut encoded: Vec<char> = Vec::with_capacity(length);
 
-    while hash > 0 {
+    for _ in 0..length {
         encoded.push(ALPHABET[(hash % 62) as usize]);
         hash /= 62;
     }
 
-    encoded
+    encoded.iter().rev().collect()
+}
+
+/// Derives a short code for `long_url`, probing for a free one on collision.
+///
+/// Re-adding a `long_url` that already has a live entry is idempotent: its
+/// existing short code is returned unchanged. If the code is taken by a
+/// *different* long URL, `long_url` is re-hashed with an incrementing counter
+/// suffix until a code with no live, differing entry is found.
+fn short_code_for<T>(long_url: &str, map: &T) -> String
+where
+    T: Persistence,
+{
+    let mut counter: u64 = 0;
+
+    loop {
+        let hash_input = if counter == 0 {
+            long_url.to_string()
+        } else {
+            format!("{}:{}", long_url, counter)
+        };
+        let short_url = encode_hash(create_hash(&hash_input), DEFAULT_CODE_LENGTH);
+
+        match map.get(&short_url) {
+            Some(existing) if existing.long_url != long_url => counter += 1,
+            _ => return short_url,
+        }
+    }
+}
+
+/// Parses a shorthand duration like `7d`, `12h`, `30m`, or `45s` into a `chrono::Duration`.
+///
+/// Returns `None` if the string doesn't end in a recognized unit or the numeric part
+/// can't be parsed.
+fn parse_duration_shorthand(raw: &str) -> Option<Duration> {
+    let (amount, unit) = raw.split_at(raw.char_indices().last()?.0);
+    let amount: i64 = amount.parse().ok()?;
+
+    match unit {
+        "d" => Some(Duration::days(amount)),
+        "h" => Some(Duration::hours(amount)),
+        "m" => Some(Duration::minutes(amount)),
+        "s" => Some(Duration::seconds(amount)),
+        _ => None,
+    }
 }
 
 /// Adds a URL to the configured persistence layer
 ///
 /// If the entry was successfully created it is also returned.
-/// If the entry wasn't successfully created, `None` is returned
 ///
-/// If a collision occurs, the method will `panic`
+/// Re-adding the same `long_url` is idempotent and returns the existing entry
+/// unchanged. A collision with a *different* long URL is resolved by probing
+/// for a free short code rather than panicking.
 ///
 /// # Example
 /// ```
 /// let mut url_map: HashMap<String, UrlEntry> = HashMap::new();
 /// let entry: UrlEntry = add_url("https://www.google.com", url_map);
 /// ```
-fn add_url<T>(long_url: &str, map: &mut T) -> Option<UrlEntry>
+fn add_url<T>(
+    long_url: &str,
+    map: &mut T,
+    expiration_date: Option<chrono::DateTime<Utc>>,
+) -> Option<UrlEntry>
 where
     T: Persistence,
 {
-    let hash = create_hash(long_url)?;
-    let short_url = encode_hash(hash);
-
-    if map.contains_key(&short_url) {
-        panic!("There was a collision");
-    } else {
-        let entry = UrlEntry::new(long_url, &short_url);
-        map.insert(short_url.clone(), entry.clone());
-        return Some(entry);
+    let short_url = short_code_for(long_url, map);
+
+    if let Some(existing) = map.get(&short_url) {
+        if existing.long_url == long_url {
+            return Some(existing);
+        }
     }
+
+    let mut entry = UrlEntry::new(long_url, &short_url);
+    entry.expiration_date = expiration_date;
+    map.insert(short_url.clone(), entry.clone());
+    Some(entry)
+}
+
+/// Adds a URL to the configured persistence layer in encrypted form
+///
+/// The long URL is never written to `map` in plaintext: it's encrypted with a
+/// freshly generated XChaCha20-Poly1305 key, and only the ciphertext is stored.
+/// The returned short code is `<short_url>.<key>` and is the only place the key
+/// is ever held, so it must be given to whoever needs to resolve the link.
+///
+/// A collision with an existing short code is resolved by probing for a free
+/// one rather than panicking.
+fn add_private_url<T>(
+    long_url: &str,
+    map: &mut T,
+    expiration_date: Option<chrono::DateTime<Utc>>,
+) -> Option<String>
+where
+    T: Persistence,
+{
+    let encrypted = crypto::encrypt(long_url);
+    let short_url = short_code_for(&encrypted.ciphertext, map);
+    let mut entry = UrlEntry::new(&encrypted.ciphertext, &short_url);
+    entry.expiration_date = expiration_date;
+    entry.is_private = true;
+    map.insert(short_url.clone(), entry);
+
+    Some(format!("{}.{}", short_url, encrypted.key))
 }
 
 /// Adds a `UrlEntry` to the configured persistence layer
@@ -73,15 +165,18 @@ fn add_entry<'a, T: 'a>(entry: UrlEntry, map: &'a mut T) -> Option<&'a UrlEntry>
 where
     T: Persistence,
 {
-    let short_url = encode_hash(create_hash(&entry.long_url)?);
+    let short_url = encode_hash(create_hash(&entry.long_url), DEFAULT_CODE_LENGTH);
     map.insert(
         short_url.clone(),
         UrlEntry {
             long_url: entry.long_url.clone(),
             short_url,
             expiration_date: entry.expiration_date,
-            creation_date: Instant::now(),
+            creation_date: Utc::now(),
             author: entry.author,
+            is_private: entry.is_private,
+            access_count: entry.access_count,
+            last_accessed: entry.last_accessed,
         },
     )
 }
@@ -124,12 +219,24 @@ where
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+
+    /// Persistence backend to use: `memory`, `file`, or `rocksdb`. Falls back to
+    /// the `TINIFIER_STORE` env var, then `file`.
+    #[clap(long, global = true)]
+    store: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Add {
         long_url: String,
+        /// Expire the link after the given duration, e.g. `7d`, `12h`, `30m`
+        #[clap(long)]
+        expires_in: Option<String>,
+        /// Encrypt the long URL; the decryption key is only ever returned in the
+        /// short code and is never persisted
+        #[clap(long)]
+        private: bool,
     },
     View {
         short_url: String,
@@ -144,18 +251,55 @@ enum Commands {
     Remove {
         short_url: String,
     },
+    /// Drop expired entries from the persistence layer
+    Prune,
+    /// Search persisted links, e.g. `author:alice before:2024-01-01 google`
+    Search {
+        query: String,
+    },
+    /// Run an HTTP redirect daemon, resolving `/<short_url>` to its long URL
+    Serve {
+        /// Port to listen on
+        #[clap(long, default_value_t = 8080)]
+        port: u16,
+    },
 }
 
 fn main() {
     let args = Cli::parse();
 
-    let mut persistence = persistence::File::new();
+    let backend = args
+        .store
+        .clone()
+        .or_else(|| std::env::var("TINIFIER_STORE").ok())
+        .unwrap_or_else(|| "file".to_string())
+        .parse::<persistence::Backend>()
+        .unwrap_or_else(|e| panic!("{}", e));
+    let mut persistence = persistence::Store::open(backend);
 
     let added = "ADDED:\n".green().bold();
 
     match &args.command {
-        Commands::Add { long_url } => {
-            let a = add_url(long_url, &mut persistence).unwrap();
+        Commands::Add {
+            long_url,
+            expires_in,
+            private,
+        } => {
+            let expiration_date = expires_in.as_deref().map(|raw| {
+                let duration = parse_duration_shorthand(raw)
+                    .unwrap_or_else(|| panic!("invalid --expires-in value: {}", raw));
+                Utc::now() + duration
+            });
+
+            if *private {
+                let short_url =
+                    add_private_url(long_url, &mut persistence, expiration_date).unwrap();
+                let short_url_label = "\tShort URL: ".truecolor(135, 135, 135);
+                println!("{}\n{}{}\n", added, short_url_label, short_url);
+                return;
+            }
+
+            let a = add_url(long_url, &mut persistence, expiration_date).unwrap();
 
             let long_url = "\tLong URL: ".truecolor(135, 135, 135);
             let short_url = "\tShort URL: ".truecolor(135, 135, 135);
@@ -178,7 +322,22 @@ fn main() {
             );
         }
         Commands::View { short_url, long } => {
-            if let Some(entry) = persistence.get(short_url) {
+            let (code, key) = match short_url.split_once('.') {
+                Some((code, key)) => (code, Some(key)),
+                None => (short_url.as_str(), None),
+            };
+
+            if let Some(mut entry) = persistence.get(code) {
+                if entry.is_private {
+                    match key.map(|key| crypto::decrypt(&entry.long_url, key)) {
+                        Some(Ok(plaintext)) => entry.long_url = plaintext,
+                        Some(Err(_)) | None => {
+                            println!("{}", "Not Found".red().bold());
+                            return;
+                        }
+                    }
+                }
+
                 if *long {
                     println!("{} => {:?}", short_url.green(), entry);
                 } else {
@@ -198,6 +357,25 @@ fn main() {
         Commands::Remove { short_url } => {
             println!("Removing {:?}", short_url);
         }
+        Commands::Prune => {
+            persistence.prune();
+            println!("{}", "Pruned expired entries".green());
+        }
+        Commands::Search { query } => {
+            let index = search::SearchIndex::build(persistence.all());
+            let results = index.search(query);
+
+            if results.is_empty() {
+                println!("{}", "Not Found".red().bold());
+            } else {
+                for entry in results {
+                    println!("{}", entry);
+                }
+            }
+        }
+        Commands::Serve { port } => {
+            serve::run(*port, &mut persistence);
+        }
     }
 }
 
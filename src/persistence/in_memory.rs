@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use super::Persistence;
+use crate::url_entry::UrlEntry;
+
+pub struct InMemory {
+    map: HashMap<String, UrlEntry>,
+}
+
+impl InMemory {
+    pub fn new() -> Self {
+        InMemory {
+            map: HashMap::new(),
+        }
+    }
+}
+
+impl Persistence for InMemory {
+    fn insert<S>(&mut self, short_url: S, entry: UrlEntry) -> Option<&UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        self.map.insert(short_url.clone().into(), entry);
+        self.map.get(&short_url.into())
+    }
+
+    fn get<S>(&self, short_url: S) -> Option<UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        let entry = self.map.get(&short_url.into())?.to_owned();
+        if entry.is_expired() {
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn remove<S>(&mut self, short_url: S) -> Option<UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        self.map.remove(&short_url.into())
+    }
+
+    fn contains_key<S>(&self, short_url: S) -> bool
+    where
+        S: Into<String> + Clone,
+    {
+        self.map.contains_key(&short_url.into())
+    }
+
+    fn all(&self) -> Vec<UrlEntry> {
+        self.map.values().cloned().collect()
+    }
+
+    fn prune(&mut self) {
+        self.map.retain(|_, entry| !entry.is_expired());
+    }
+}
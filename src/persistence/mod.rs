@@ -1,8 +1,13 @@
-use std::fs::OpenOptions;
-use std::io::{BufRead, BufReader, Write};
-use std::{collections::HashMap, path::Path};
+mod file;
+mod in_memory;
+mod rocks_db;
+
+pub use file::File;
+pub use in_memory::InMemory;
+pub use rocks_db::RocksDb;
 
 use crate::url_entry::UrlEntry;
+use std::str::FromStr;
 
 pub trait Persistence {
     fn insert<S>(&mut self, short_url: S, entry: UrlEntry) -> Option<&UrlEntry>
@@ -17,142 +22,109 @@ pub trait Persistence {
     fn contains_key<S>(&self, short_url: S) -> bool
     where
         S: Into<String> + Clone;
+    /// Returns every persisted entry, live or expired.
+    fn all(&self) -> Vec<UrlEntry>;
+    /// Drops every expired entry from the store.
+    fn prune(&mut self);
 }
 
-pub struct InMemory {
-    map: HashMap<String, UrlEntry>,
+/// Which `Persistence` implementor to use, selectable via `--store` or `TINIFIER_STORE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Memory,
+    File,
+    RocksDb,
 }
 
-impl Persistence for InMemory {
-    fn insert<S>(&mut self, short_url: S, entry: UrlEntry) -> Option<&UrlEntry>
-    where
-        S: Into<String> + Clone,
-    {
-        self.map.insert(short_url.clone().into(), entry);
-        self.map.get(&short_url.into())
-    }
+impl FromStr for Backend {
+    type Err = String;
 
-    fn get<S>(&self, short_url: S) -> Option<UrlEntry>
-    where
-        S: Into<String> + Clone,
-    {
-        Some(self.map.get(&short_url.into())?.to_owned())
-    }
-
-    fn remove<S>(&mut self, short_url: S) -> Option<UrlEntry>
-    where
-        S: Into<String> + Clone,
-    {
-        self.map.remove(&short_url.into())
-    }
-
-    fn contains_key<S>(&self, short_url: S) -> bool
-    where
-        S: Into<String> + Clone,
-    {
-        true
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(Backend::Memory),
+            "file" => Ok(Backend::File),
+            "rocksdb" => Ok(Backend::RocksDb),
+            other => Err(format!("unknown store backend: {}", other)),
+        }
     }
 }
 
-pub struct File<'a> {
-    map: HashMap<String, UrlEntry>,
-    file_location: &'a Path,
+/// A `Persistence` implementor chosen at runtime by [`Backend`], so CLI commands
+/// can stay backend-agnostic.
+pub enum Store<'a> {
+    Memory(InMemory),
+    File(File<'a>),
+    RocksDb(RocksDb),
 }
 
-impl File<'_> {
-    pub fn new() -> Self {
-        if let Some(file) = OpenOptions::new().read(true).open("/tmp/tinifier").ok() {
-            let lines = BufReader::new(file)
-                .lines()
-                .filter_map(|line| line.ok())
-                .filter_map(|line| {
-                    if let Some(entry) = line.parse::<UrlEntry>().ok() {
-                        return Some((entry.short_url.clone(), entry));
-                    } else {
-                        return None;
-                    }
-                })
-                .collect::<HashMap<String, UrlEntry>>();
-            return File {
-                map: lines,
-                file_location: Path::new("/tmp/tinifier"),
-            };
-        }
-        File {
-            map: HashMap::new(),
-            file_location: Path::new("/tmp/tinifier"),
+impl Store<'_> {
+    pub fn open(backend: Backend) -> Self {
+        match backend {
+            Backend::Memory => Store::Memory(InMemory::new()),
+            Backend::File => Store::File(File::new()),
+            Backend::RocksDb => Store::RocksDb(RocksDb::new("/tmp/tinifier.rocksdb")),
         }
     }
 }
 
-impl Persistence for File<'_> {
+impl Persistence for Store<'_> {
     fn insert<S>(&mut self, short_url: S, entry: UrlEntry) -> Option<&UrlEntry>
     where
         S: Into<String> + Clone,
     {
-        // Insert into cache
-        self.map.insert(short_url.clone().into(), entry.clone());
-        // Write to file
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(self.file_location)
-            .expect("file cannot be opened");
-
-        // If there's an error writing, remove from hashmap
-        if let Err(e) = writeln!(file, "{}", &entry.to_file_string()) {
-            self.map.remove(&short_url.clone().into());
-            eprintln!("Couldn't write to file: {}", e);
+        match self {
+            Store::Memory(store) => store.insert(short_url, entry),
+            Store::File(store) => store.insert(short_url, entry),
+            Store::RocksDb(store) => store.insert(short_url, entry),
         }
-
-        // Return a reference to the entry we created
-        self.map.get(&short_url.into())
     }
 
     fn get<S>(&self, short_url: S) -> Option<UrlEntry>
     where
         S: Into<String> + Clone,
     {
-        // Get from the cache
-        Some(self.map.get(&short_url.into())?.to_owned())
+        match self {
+            Store::Memory(store) => store.get(short_url),
+            Store::File(store) => store.get(short_url),
+            Store::RocksDb(store) => store.get(short_url),
+        }
     }
 
     fn remove<S>(&mut self, short_url: S) -> Option<UrlEntry>
     where
         S: Into<String> + Clone,
     {
-        // Open the persistence file
-        let file = OpenOptions::new()
-            .read(true)
-            .open(self.file_location)
-            .expect("file doesn't exist");
-
-        // Read from the file, filtering out any lines containing the short_url
-        // Then collect the result into a new string (AH BAD)
-        let lines = BufReader::new(file)
-            .lines()
-            .filter_map(|line| line.ok())
-            .filter_map(|line| {
-                if line.contains(&short_url.clone().into()) {
-                    None
-                } else {
-                    Some(line)
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        // Write the updated string to the file
-        std::fs::write(self.file_location, lines).expect("Writing failed");
-
-        // Remove the entry from the cache, returning it
-        self.map.remove(&short_url.into())
+        match self {
+            Store::Memory(store) => store.remove(short_url),
+            Store::File(store) => store.remove(short_url),
+            Store::RocksDb(store) => store.remove(short_url),
+        }
     }
 
     fn contains_key<S>(&self, short_url: S) -> bool
     where
         S: Into<String> + Clone,
     {
-        false
+        match self {
+            Store::Memory(store) => store.contains_key(short_url),
+            Store::File(store) => store.contains_key(short_url),
+            Store::RocksDb(store) => store.contains_key(short_url),
+        }
+    }
+
+    fn all(&self) -> Vec<UrlEntry> {
+        match self {
+            Store::Memory(store) => store.all(),
+            Store::File(store) => store.all(),
+            Store::RocksDb(store) => store.all(),
+        }
+    }
+
+    fn prune(&mut self) {
+        match self {
+            Store::Memory(store) => store.prune(),
+            Store::File(store) => store.prune(),
+            Store::RocksDb(store) => store.prune(),
+        }
     }
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use rocksdb::{IteratorMode, DB};
+
+use super::Persistence;
+use crate::url_entry::UrlEntry;
+
+/// A `Persistence` backend on top of RocksDB.
+///
+/// Every entry is serialized (serde) and stored under its short code as the
+/// key, giving O(1) point `get`/`remove`/`contains_key` and an atomic
+/// overwrite on `insert` with no full-file rewrites. An in-memory `cache`,
+/// loaded when the store is opened and updated on `insert`/`remove`, mirrors
+/// the store so `insert` can hand back a `&UrlEntry`, same as the other
+/// backends; `all` and `prune` read it, while `get` and `contains_key` read
+/// RocksDB directly.
+pub struct RocksDb {
+    db: DB,
+    cache: HashMap<String, UrlEntry>,
+}
+
+impl RocksDb {
+    pub fn new(path: &str) -> Self {
+        let db = DB::open_default(path).expect("failed to open RocksDB store");
+
+        let cache = db
+            .iterator(IteratorMode::Start)
+            .filter_map(|item| item.ok())
+            .filter_map(|(key, value)| {
+                let entry = serde_json::from_slice::<UrlEntry>(&value).ok()?;
+                Some((String::from_utf8_lossy(&key).into_owned(), entry))
+            })
+            .collect();
+
+        RocksDb { db, cache }
+    }
+}
+
+impl Persistence for RocksDb {
+    fn insert<S>(&mut self, short_url: S, entry: UrlEntry) -> Option<&UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        let key: String = short_url.into();
+        let bytes = serde_json::to_vec(&entry).expect("UrlEntry should always serialize");
+        self.db
+            .put(key.as_bytes(), bytes)
+            .expect("RocksDB put failed");
+
+        self.cache.insert(key.clone(), entry);
+        self.cache.get(&key)
+    }
+
+    fn get<S>(&self, short_url: S) -> Option<UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        let key: String = short_url.into();
+        let bytes = self.db.get(key.as_bytes()).expect("RocksDB get failed")?;
+        let entry = serde_json::from_slice::<UrlEntry>(&bytes).ok()?;
+        if entry.is_expired() {
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn remove<S>(&mut self, short_url: S) -> Option<UrlEntry>
+    where
+        S: Into<String> + Clone,
+    {
+        let key: String = short_url.into();
+        self.db
+            .delete(key.as_bytes())
+            .expect("RocksDB delete failed");
+        self.cache.remove(&key)
+    }
+
+    fn contains_key<S>(&self, short_url: S) -> bool
+    where
+        S: Into<String> + Clone,
+    {
+        let key: String = short_url.into();
+        matches!(self.db.get(key.as_bytes()), Ok(Some(_)))
+    }
+
+    fn all(&self) -> Vec<UrlEntry> {
+        self.cache.values().cloned().collect()
+    }
+
+    fn prune(&mut self) {
+        let expired: Vec<String> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| entry.is_expired())
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in expired {
+            self.cache.remove(&key);
+            self.db
+                .delete(key.as_bytes())
+                .expect("RocksDB delete failed");
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::url_entry::UrlEntry;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Clause {
+    Term(String),
+    Author(String),
+    Before(DateTime<Utc>),
+    After(DateTime<Utc>),
+}
+
+/// Parses a query like `author:alice before:2024-01-01 google` into typed clauses.
+///
+/// Bare terms match, case-insensitively, substrings of a single alphanumeric
+/// token of `long_url`; a term that spans punctuation (e.g. `google.com`) matches
+/// nothing. `author:` filters by author; and `before:`/`after:` filter by
+/// `creation_date`. Dates are `YYYY-MM-DD`. Clauses
+/// that fail to parse (e.g. a malformed date) are dropped rather than erroring,
+/// matching a best-effort query language.
+fn parse_query(query: &str) -> Vec<Clause> {
+    query
+        .split_whitespace()
+        .filter_map(|token| {
+            if let Some(author) = token.strip_prefix("author:") {
+                Some(Clause::Author(author.to_lowercase()))
+            } else if let Some(date) = token.strip_prefix("before:") {
+                parse_date(date).map(Clause::Before)
+            } else if let Some(date) = token.strip_prefix("after:") {
+                parse_date(date).map(Clause::After)
+            } else {
+                Some(Clause::Term(token.to_lowercase()))
+            }
+        })
+        .collect()
+}
+
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+fn tokenize(long_url: &str) -> HashSet<String> {
+    long_url
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// An in-memory inverted index over persisted `UrlEntry` values, supporting the
+/// small query language parsed by [`parse_query`].
+pub struct SearchIndex {
+    token_index: HashMap<String, HashSet<String>>,
+    author_index: HashMap<String, HashSet<String>>,
+    by_creation: Vec<(DateTime<Utc>, String)>,
+    entries: HashMap<String, UrlEntry>,
+}
+
+impl SearchIndex {
+    /// Builds an index over `entries`, tokenizing each `long_url` on non-alphanumeric
+    /// characters.
+    pub fn build(entries: Vec<UrlEntry>) -> Self {
+        let mut token_index: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut author_index: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut by_creation: Vec<(DateTime<Utc>, String)> = Vec::new();
+        let mut by_code: HashMap<String, UrlEntry> = HashMap::new();
+
+        for entry in entries {
+            for token in tokenize(&entry.long_url) {
+                token_index
+                    .entry(token)
+                    .or_default()
+                    .insert(entry.short_url.clone());
+            }
+            author_index
+                .entry(entry.author.to_lowercase())
+                .or_default()
+                .insert(entry.short_url.clone());
+            by_creation.push((entry.creation_date, entry.short_url.clone()));
+            by_code.insert(entry.short_url.clone(), entry);
+        }
+
+        by_creation.sort_by(|a, b| b.0.cmp(&a.0));
+
+        SearchIndex {
+            token_index,
+            author_index,
+            by_creation,
+            entries: by_code,
+        }
+    }
+
+    /// Runs `query` against the index, intersecting the candidate short codes
+    /// from each clause and returning the matching entries ranked by creation
+    /// date descending.
+    pub fn search(&self, query: &str) -> Vec<UrlEntry> {
+        let mut candidates: Option<HashSet<String>> = None;
+
+        for clause in parse_query(query) {
+            let matches: HashSet<String> = match clause {
+                Clause::Term(term) => self
+                    .token_index
+                    .iter()
+                    .filter(|(token, _)| token.contains(term.as_str()))
+                    .flat_map(|(_, codes)| codes.iter().cloned())
+                    .collect(),
+                Clause::Author(author) => {
+                    self.author_index.get(&author).cloned().unwrap_or_default()
+                }
+                Clause::Before(date) => self
+                    .by_creation
+                    .iter()
+                    .filter(|(created, _)| *created < date)
+                    .map(|(_, code)| code.clone())
+                    .collect(),
+                Clause::After(date) => self
+                    .by_creation
+                    .iter()
+                    .filter(|(created, _)| *created > date)
+                    .map(|(_, code)| code.clone())
+                    .collect(),
+            };
+
+            candidates = Some(match candidates {
+                Some(existing) => existing.intersection(&matches).cloned().collect(),
+                None => matches,
+            });
+        }
+
+        let candidates = candidates.unwrap_or_default();
+
+        self.by_creation
+            .iter()
+            .filter(|(_, code)| candidates.contains(code))
+            .filter_map(|(_, code)| self.entries.get(code).cloned())
+            .collect()
+    }
+}
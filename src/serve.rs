@@ -0,0 +1,35 @@
+use tiny_http::{Header, Response, Server, StatusCode};
+
+use crate::persistence::Persistence;
+
+/// Runs an HTTP redirect daemon on `port`: a request to `/<short_url>` looks
+/// the code up through `persistence`, records the hit, and replies with a
+/// `302` pointing at `long_url`. Unknown or expired codes get a `404`.
+///
+/// This blocks forever, serving requests one at a time.
+pub fn run<T: Persistence>(port: u16, persistence: &mut T) {
+    let server = Server::http(("0.0.0.0", port))
+        .unwrap_or_else(|e| panic!("failed to bind to port {}: {}", port, e));
+
+    println!("Serving short links on http://0.0.0.0:{}", port);
+
+    for request in server.incoming_requests() {
+        let short_url = request.url().trim_start_matches('/').to_string();
+
+        let response = match persistence.get(&short_url) {
+            Some(mut entry) => {
+                entry.record_access();
+                persistence.insert(short_url, entry.clone());
+
+                let location = Header::from_bytes(&b"Location"[..], entry.long_url.as_bytes())
+                    .expect("Location header value should always be valid ASCII");
+                Response::empty(StatusCode(302)).with_header(location)
+            }
+            None => Response::empty(StatusCode(404)),
+        };
+
+        if let Err(e) = request.respond(response) {
+            eprintln!("Couldn't write response: {}", e);
+        }
+    }
+}
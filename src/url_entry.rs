@@ -1,22 +1,31 @@
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::env;
 use std::str::FromStr;
-use std::{env, time::Instant};
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct UrlEntry {
     pub long_url: String,
     pub short_url: String,
-    pub expiration_date: Option<Instant>,
-    pub creation_date: Instant,
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub creation_date: DateTime<Utc>,
     pub author: String,
+    /// When `true`, `long_url` holds an encrypted blob rather than plaintext and can
+    /// only be read back by whoever holds the key carried in the short code.
+    pub is_private: bool,
+    /// Number of times `serve` has resolved this short code.
+    pub access_count: u64,
+    /// When `serve` last resolved this short code.
+    pub last_accessed: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UrlEntryRequest {
     pub long_url: Option<String>,
     pub short_url: Option<String>,
-    pub expiration_date: Option<Instant>,
+    pub expiration_date: Option<DateTime<Utc>>,
     pub author: String,
 }
 
@@ -31,11 +40,28 @@ impl UrlEntry {
             long_url: long_url.to_string(),
             short_url: short_url.to_string(),
             expiration_date: None,
-            creation_date: Instant::now(),
+            creation_date: Utc::now(),
             author: env::var("USER").unwrap_or("SYSTEM".to_string()),
+            is_private: false,
+            access_count: 0,
+            last_accessed: None,
         }
     }
 
+    /// Returns `true` if this entry has an `expiration_date` that has already passed.
+    pub fn is_expired(&self) -> bool {
+        self.expiration_date
+            .map(|expiration| expiration < Utc::now())
+            .unwrap_or(false)
+    }
+
+    /// Records a resolution of this short code: bumps `access_count` and sets
+    /// `last_accessed` to now.
+    pub fn record_access(&mut self) {
+        self.access_count += 1;
+        self.last_accessed = Some(Utc::now());
+    }
+
     pub fn merge_with(&mut self, entry: &UrlEntryRequest) {
         self.long_url = entry.long_url.clone().unwrap_or(self.long_url.clone());
         self.short_url = self.short_url.clone();
@@ -65,13 +91,32 @@ impl UrlEntry {
             expiration_date,
             creation_date,
             author,
+            is_private: self.is_private,
+            access_count: self.access_count,
+            last_accessed: self.last_accessed,
         }
     }
 
     pub fn to_file_string(&self) -> String {
+        let expiration_date = self
+            .expiration_date
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default();
+        let last_accessed = self
+            .last_accessed
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default();
+
         format!(
-            "{}:{},{:?},{:?},{}",
-            self.short_url, self.long_url, self.expiration_date, self.creation_date, self.author
+            "{}:{},{},{},{},{},{},{}",
+            self.short_url,
+            self.long_url,
+            expiration_date,
+            self.creation_date.to_rfc3339(),
+            self.author,
+            self.is_private,
+            self.access_count,
+            last_accessed
         )
     }
 }
@@ -83,9 +128,11 @@ impl std::fmt::Display for UrlEntry {
         let expiration_date = "Expiration Date: ".truecolor(135, 135, 135);
         let creation_date = "Creation Date: ".truecolor(135, 135, 135);
         let author = "Author: ".truecolor(135, 135, 135);
+        let access_count = "Access Count: ".truecolor(135, 135, 135);
+        let last_accessed = "Last Accessed: ".truecolor(135, 135, 135);
         write!(
             f,
-            "{}{}\n{}{}\n{}{:?}\n{}{:?}\n{}{}\n",
+            "{}{}\n{}{}\n{}{:?}\n{}{:?}\n{}{}\n{}{}\n{}{:?}\n",
             long_url,
             self.long_url,
             short_url,
@@ -95,7 +142,11 @@ impl std::fmt::Display for UrlEntry {
             creation_date,
             self.creation_date,
             author,
-            self.author
+            self.author,
+            access_count,
+            self.access_count,
+            last_accessed,
+            self.last_accessed
         )
     }
 }
@@ -105,12 +156,12 @@ impl FromStr for UrlEntry {
 
     /// Takes a `UrlEntry` of the form:
     /// ```
-    /// <short_url>:<long-url>,<expiration_date>,<creation_date>,<author>
+    /// <short_url>:<long-url>,<expiration_date>,<creation_date>,<author>,<is_private>,<access_count>,<last_accessed>
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         lazy_static! {
             static ref URL_ENTRY_RE: Regex =
-                Regex::new(r"^(?P<short>[a-zA-Z0-9]+):(?P<long>\w+),(?P<expiration>.*),(?P<creation>.*),(?P<author>\w+)$").unwrap();
+                Regex::new(r"^(?P<short>[a-zA-Z0-9]+):(?P<long>[^,]+),(?P<expiration>.*),(?P<creation>.*),(?P<author>\w+),(?P<private>true|false),(?P<access_count>\d+),(?P<last_accessed>.*)$").unwrap();
         }
 
         if let Some(captures) = URL_ENTRY_RE.captures(s) {
@@ -129,14 +180,27 @@ impl FromStr for UrlEntry {
             let expiration_date = captures
                 .name("expiration")
                 .expect("expiration exists")
-                .as_str()
-                .to_string();
-
-            let creation_date = captures
-                .name("creation")
-                .expect("creation exists")
-                .as_str()
-                .to_string();
+                .as_str();
+
+            let expiration_date = if expiration_date.is_empty() {
+                None
+            } else {
+                Some(
+                    DateTime::parse_from_rfc3339(expiration_date)
+                        .map_err(|_| EntryParseError {
+                            message: "invalid expiration_date".to_string(),
+                        })?
+                        .with_timezone(&Utc),
+                )
+            };
+
+            let creation_date = captures.name("creation").expect("creation exists").as_str();
+
+            let creation_date = DateTime::parse_from_rfc3339(creation_date)
+                .map_err(|_| EntryParseError {
+                    message: "invalid creation_date".to_string(),
+                })?
+                .with_timezone(&Utc);
 
             let author = captures
                 .name("author")
@@ -144,12 +208,43 @@ impl FromStr for UrlEntry {
                 .as_str()
                 .to_string();
 
+            let is_private = captures.name("private").expect("private exists").as_str() == "true";
+
+            let access_count = captures
+                .name("access_count")
+                .expect("access_count exists")
+                .as_str()
+                .parse::<u64>()
+                .map_err(|_| EntryParseError {
+                    message: "invalid access_count".to_string(),
+                })?;
+
+            let last_accessed = captures
+                .name("last_accessed")
+                .expect("last_accessed exists")
+                .as_str();
+
+            let last_accessed = if last_accessed.is_empty() {
+                None
+            } else {
+                Some(
+                    DateTime::parse_from_rfc3339(last_accessed)
+                        .map_err(|_| EntryParseError {
+                            message: "invalid last_accessed".to_string(),
+                        })?
+                        .with_timezone(&Utc),
+                )
+            };
+
             Ok(UrlEntry {
                 short_url,
                 long_url,
-                expiration_date: None,
-                creation_date: Instant::now(),
+                expiration_date,
+                creation_date,
                 author,
+                is_private,
+                access_count,
+                last_accessed,
             })
         } else {
             Err(EntryParseError {